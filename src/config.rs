@@ -0,0 +1,204 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::Error;
+
+/// Default config location: `~/.config/acs/acs.toml`.
+fn default_config_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".config/acs/acs.toml")
+}
+
+/// User-facing defaults, loaded from TOML and overridable by CLI flags.
+///
+/// Threaded through `print_cpus`, `print_cpu` and `print_cpu_usages` in
+/// `display`; the other print functions there still take a `raw: bool`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    pub governor: Option<String>,
+    pub min_freq: Option<i32>,
+    pub max_freq: Option<i32>,
+    /// Temperature (millidegrees) at which a core is shown as a warning,
+    /// used when the core's hwmon sensor reports no max threshold.
+    pub temp_warn: i32,
+    /// Temperature (millidegrees) at which a core is shown as critical,
+    /// used when the core's hwmon sensor reports no critical threshold.
+    pub temp_crit: i32,
+    pub poll_interval_ms: u64,
+    pub raw: bool,
+    pub basic: bool,
+    /// Show a per-core trend sparkline instead of the numeric-only readout.
+    pub sparkline: bool,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            governor: None,
+            min_freq: None,
+            max_freq: None,
+            temp_warn: 40_000,
+            temp_crit: 60_000,
+            poll_interval_ms: 1000,
+            raw: false,
+            basic: false,
+            sparkline: false,
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config at `path` (or the default `~/.config/acs/acs.toml`
+    /// when `path` is `None`), writing out the defaults first if no config
+    /// file exists yet.
+    pub fn load(path: Option<&Path>) -> Result<Config, Error> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => default_config_path(),
+        };
+
+        if !path.exists() {
+            let config = Config::default();
+            config.save(&path)?;
+            return Ok(config);
+        }
+
+        let contents = fs::read_to_string(&path)?;
+
+        toml::from_str(&contents)
+            .map_err(|e| Error::from(io::Error::new(io::ErrorKind::InvalidData, e)))
+    }
+
+    fn save(&self, path: &Path) -> Result<(), Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let contents = toml::to_string_pretty(self)
+            .map_err(|e| Error::from(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+        fs::File::create(path)?.write_all(contents.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// CLI flags always win over whatever the config file set.
+    pub fn apply_overrides(&mut self, overrides: ConfigOverrides) {
+        if let Some(raw) = overrides.raw {
+            self.raw = raw;
+        }
+        if let Some(basic) = overrides.basic {
+            self.basic = basic;
+        }
+        if overrides.governor.is_some() {
+            self.governor = overrides.governor;
+        }
+        if let Some(min_freq) = overrides.min_freq {
+            self.min_freq = Some(min_freq);
+        }
+        if let Some(max_freq) = overrides.max_freq {
+            self.max_freq = Some(max_freq);
+        }
+        if let Some(temp_warn) = overrides.temp_warn {
+            self.temp_warn = temp_warn;
+        }
+        if let Some(temp_crit) = overrides.temp_crit {
+            self.temp_crit = temp_crit;
+        }
+        if let Some(poll_interval_ms) = overrides.poll_interval_ms {
+            self.poll_interval_ms = poll_interval_ms;
+        }
+        if let Some(sparkline) = overrides.sparkline {
+            self.sparkline = sparkline;
+        }
+    }
+}
+
+/// CLI flags that may override the loaded `Config`, one field per
+/// overridable `Config` field. `None` means "not passed on the command
+/// line, leave the config's value alone".
+#[derive(Debug, Clone, Default)]
+pub struct ConfigOverrides {
+    pub raw: Option<bool>,
+    pub basic: Option<bool>,
+    pub governor: Option<String>,
+    pub min_freq: Option<i32>,
+    pub max_freq: Option<i32>,
+    pub temp_warn: Option<i32>,
+    pub temp_crit: Option<i32>,
+    pub poll_interval_ms: Option<u64>,
+    pub sparkline: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Each test gets its own path under the system temp dir, keyed by PID
+    /// and test name, so parallel test runs don't clobber each other.
+    fn scratch_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("acs-config-test-{}-{}.toml", std::process::id(), name))
+    }
+
+    #[test]
+    fn missing_config_is_created_with_defaults() {
+        let path = scratch_path("missing");
+        let _ = fs::remove_file(&path);
+
+        let config = Config::load(Some(&path)).unwrap();
+
+        assert_eq!(config.temp_warn, Config::default().temp_warn);
+        assert!(path.exists());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let path = scratch_path("round-trip");
+        let config = Config {
+            basic: true,
+            temp_crit: 70_000,
+            ..Config::default()
+        };
+        config.save(&path).unwrap();
+
+        let loaded = Config::load(Some(&path)).unwrap();
+
+        assert!(loaded.basic);
+        assert_eq!(loaded.temp_crit, 70_000);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn corrupt_config_returns_error_instead_of_panicking() {
+        let path = scratch_path("corrupt");
+        fs::write(&path, "this is not valid toml = = =").unwrap();
+
+        let result = Config::load(Some(&path));
+
+        assert!(result.is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn overrides_only_apply_when_set() {
+        let mut config = Config::default();
+        config.apply_overrides(ConfigOverrides {
+            basic: Some(true),
+            temp_crit: Some(80_000),
+            ..ConfigOverrides::default()
+        });
+
+        assert!(config.basic);
+        assert_eq!(config.temp_crit, 80_000);
+        assert!(!config.raw);
+        assert_eq!(config.governor, None);
+        assert_eq!(config.temp_warn, Config::default().temp_warn);
+    }
+}
@@ -1,8 +1,14 @@
+use std::collections::HashMap;
+use std::fs;
 use std::fs::File;
-use std::io::{Read, Write};
-use std::path::Path;
-
-use super::display::{print_cpu, render_cpu};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use super::config::Config;
+use super::display::{print_cpu, print_cpu_sparkline, print_cpu_usages, render_cpu, History};
+use super::hwmon::HwmonMap;
+use super::sysfs::FromRead;
 use super::system::{calculate_cpu_percent, ProcStat};
 use super::Error;
 
@@ -10,8 +16,8 @@ use super::Error;
 use mockall::{automock, predicate::*};
 #[cfg_attr(test, automock)]
 pub trait Speed {
-    fn read_int(&mut self, sub_path: &str) -> i32;
-    fn read_str(&mut self, sub_path: &str) -> String;
+    fn read_int(&mut self, sub_path: &str) -> Result<i32, Error>;
+    fn read_str(&mut self, sub_path: &str) -> Result<String, Error>;
     fn read_temp(&mut self, sub_path: &str) -> Result<i32, Error>;
     fn write_value(&mut self, value: WritableValue) -> Result<(), Error>;
     fn update(&mut self) -> Result<(), Error>;
@@ -19,13 +25,13 @@ pub trait Speed {
     fn init_cpu(&mut self) -> Result<(), Error>;
     fn set_max(&mut self, max: i32) -> Result<(), Error>;
     fn set_min(&mut self, min: i32) -> Result<(), Error>;
-    fn get_max(&mut self);
-    fn get_min(&mut self);
-    fn get_cur(&mut self);
+    fn get_max(&mut self) -> Result<(), Error>;
+    fn get_min(&mut self) -> Result<(), Error>;
+    fn get_cur(&mut self) -> Result<(), Error>;
     fn get_temp(&mut self) -> Result<(), Error>;
     fn get_gov(&mut self) -> Result<(), Error>;
     fn set_gov(&mut self, gov: String) -> Result<(), Error>;
-    fn print(&self);
+    fn print(&self, config: &Config);
     fn render(&self) -> String;
 }
 
@@ -37,8 +43,18 @@ pub struct CPU {
     pub min_freq: i32,
     pub cur_freq: i32,
     pub cur_temp: i32,
+    pub crit_temp: i32,
+    pub max_temp: i32,
     pub cur_usage: f32,
     pub gov: String,
+    temp_sensor: Option<PathBuf>,
+    /// Set once `init_temp_sensor` has run, even if it found no sensor, so a
+    /// core with no hwmon match isn't re-scanned on every monitor tick.
+    temp_sensor_checked: bool,
+    /// Rolling window of this core's recent `cur_freq` samples, rendered as
+    /// a trend sparkline next to the numeric readout when `config.sparkline`
+    /// is set.
+    freq_history: History,
 }
 
 #[derive(PartialEq)]
@@ -50,55 +66,26 @@ pub enum WritableValue {
 
 impl Speed for CPU {
     /// A generic function to take a path and a single cpu (single core) and get an i32
-    fn read_int(&mut self, sub_path: &str) -> i32 {
-        let mut info: String = String::new();
-        let cpu_info_path: String = format!("/sys/devices/system/cpu/{}/{}", self.name, sub_path);
-
-        File::open(cpu_info_path)
-            .unwrap()
-            .read_to_string(&mut info)
-            .unwrap();
-
-        // Remove newline
-        info.pop();
-        info.parse::<i32>()
-            .unwrap_or_else(|e| panic!("Could not parse {}\n{}", sub_path, e))
+    fn read_int(&mut self, sub_path: &str) -> Result<i32, Error> {
+        let cpu_info_path = format!("/sys/devices/system/cpu/{}/{}", self.name, sub_path);
+        i32::from_file(cpu_info_path)
     }
 
-    fn read_str(&mut self, sub_path: &str) -> String {
-        let mut info: String = String::new();
-        let cpu_info_path: String = format!("/sys/devices/system/cpu/{}/{}", self.name, sub_path);
-
-        File::open(cpu_info_path)
-            .unwrap()
-            .read_to_string(&mut info)
-            .unwrap();
-
-        // Remove newline
-        info.pop();
-        info
+    fn read_str(&mut self, sub_path: &str) -> Result<String, Error> {
+        let cpu_info_path = format!("/sys/devices/system/cpu/{}/{}", self.name, sub_path);
+        String::from_file(cpu_info_path)
     }
 
-    fn read_temp(&mut self, sub_path: &str) -> Result<i32, Error> {
-        let mut info: String = String::new();
-        let cpu_info_path: String = format!(
-            "/sys/class/thermal/{}/{}",
-            self.name.replace("cpu", "thermal_zone"),
-            sub_path
-        );
-
-        if !Path::new(&cpu_info_path).exists() {
-            return Ok(-1);
-        }
-
-        File::open(cpu_info_path)?.read_to_string(&mut info)?;
-
-        // Remove the last character (the newline)
-        info.pop();
+    /// Reads the current temperature from this CPU's resolved hwmon sensor.
+    /// `sub_path` is kept for trait/mock compatibility but is unused, since
+    /// the sensor path is already fully resolved by `init_temp_sensor`.
+    fn read_temp(&mut self, _sub_path: &str) -> Result<i32, Error> {
+        let sensor_path = match &self.temp_sensor {
+            Some(path) => path.clone(),
+            None => return Ok(-1),
+        };
 
-        Ok(info
-            .parse::<i32>()
-            .unwrap_or_else(|e| panic!("Could not parse {}\n{}", sub_path, e)))
+        i32::from_file(sensor_path)
     }
 
     fn write_value(&mut self, value: WritableValue) -> Result<(), Error> {
@@ -129,10 +116,19 @@ impl Speed for CPU {
 
     /// Get all the attributes of a cpu
     /// These get methods write the value returned
+    ///
+    /// Skips entirely, rather than erroring, when the core is offline: the
+    /// `cpufreq`/`hwmon` attributes this reads don't exist for an offline
+    /// core, so there's nothing to update until `is_online` reports `true`
+    /// again and a later `update` picks it back up.
     fn update(&mut self) -> Result<(), Error> {
-        self.get_max();
-        self.get_min();
-        self.get_cur();
+        if !self.is_online()? {
+            return Ok(());
+        }
+
+        self.get_max()?;
+        self.get_min()?;
+        self.get_cur()?;
         self.get_temp()?;
         self.get_gov()?;
         Ok(())
@@ -161,25 +157,32 @@ impl Speed for CPU {
         Ok(())
     }
 
-    fn get_max(&mut self) {
-        self.max_freq = self.read_int("cpufreq/scaling_max_freq");
+    fn get_max(&mut self) -> Result<(), Error> {
+        self.max_freq = self.read_int("cpufreq/scaling_max_freq")?;
+        Ok(())
     }
 
-    fn get_min(&mut self) {
-        self.min_freq = self.read_int("cpufreq/scaling_min_freq");
+    fn get_min(&mut self) -> Result<(), Error> {
+        self.min_freq = self.read_int("cpufreq/scaling_min_freq")?;
+        Ok(())
     }
 
-    fn get_cur(&mut self) {
-        self.cur_freq = self.read_int("cpufreq/scaling_cur_freq");
+    fn get_cur(&mut self) -> Result<(), Error> {
+        self.cur_freq = self.read_int("cpufreq/scaling_cur_freq")?;
+        self.freq_history.push(self.cur_freq as f32);
+        Ok(())
     }
 
     fn get_temp(&mut self) -> Result<(), Error> {
+        if !self.temp_sensor_checked {
+            self.init_temp_sensor()?;
+        }
         self.cur_temp = self.read_temp("temp")?;
         Ok(())
     }
 
     fn get_gov(&mut self) -> Result<(), Error> {
-        self.gov = self.read_str("cpufreq/scaling_governor");
+        self.gov = self.read_str("cpufreq/scaling_governor")?;
         Ok(())
     }
 
@@ -189,8 +192,12 @@ impl Speed for CPU {
         Ok(())
     }
 
-    fn print(&self) {
-        print_cpu(self);
+    fn print(&self, config: &Config) {
+        if config.sparkline {
+            print_cpu_sparkline(self, &self.freq_history);
+        } else {
+            print_cpu(self, config);
+        }
     }
 
     fn render(&self) -> String {
@@ -198,18 +205,266 @@ impl Speed for CPU {
     }
 }
 
+/// The hwmon scan walks every `/sys/class/hwmon/hwmonN` directory and reads
+/// each `tempN_label`/`_crit`/`_max` file; that's identical work for every
+/// core, so it's discovered once per process and shared rather than redone
+/// by each `CPU`'s first `init_temp_sensor` call.
+static HWMON_MAP: OnceLock<HwmonMap> = OnceLock::new();
+
+fn shared_hwmon_map() -> Result<&'static HwmonMap, Error> {
+    if let Some(map) = HWMON_MAP.get() {
+        return Ok(map);
+    }
+
+    let discovered = HwmonMap::discover()?;
+    Ok(HWMON_MAP.get_or_init(|| discovered))
+}
+
+impl CPU {
+    /// Resolves this CPU's hwmon temperature sensor (falling back to the
+    /// package sensor when no per-core label exists) from the process-wide
+    /// `HWMON_MAP`, and caches its path plus critical/max thresholds so
+    /// later reads don't need to consult it again. Also records that the
+    /// lookup ran even when no sensor matched, so a core with no hwmon
+    /// driver isn't re-checked on every tick.
+    fn init_temp_sensor(&mut self) -> Result<(), Error> {
+        let sensors = shared_hwmon_map()?;
+
+        // hwmon labels sensors by physical core id, which differs from the
+        // logical CPU number on SMT machines; fall back to the logical number
+        // when the topology attribute is absent.
+        let core = self
+            .read_int("topology/core_id")
+            .map(|id| id as i8)
+            .unwrap_or(self.number);
+
+        if let Some(sensor) = sensors.sensor_for(core) {
+            self.temp_sensor = Some(sensor.input_path.clone());
+            self.crit_temp = sensor.crit_temp.unwrap_or(-1);
+            self.max_temp = sensor.max_temp.unwrap_or(-1);
+        }
+        self.temp_sensor_checked = true;
+
+        Ok(())
+    }
+
+    /// Reports whether `/sys/devices/system/cpu/cpuN/online` says this core
+    /// is currently online. Core 0 has no `online` file at all (it can
+    /// never be taken offline), so treat a missing file as online.
+    ///
+    /// `update` checks this first and skips the rest of its reads for an
+    /// offline core, so a core coming back online is picked back up the
+    /// next time `update`/`init_cpu` runs against it.
+    pub fn is_online(&self) -> Result<bool, Error> {
+        let path = format!("/sys/devices/system/cpu/{}/online", self.name);
+        if !Path::new(&path).exists() {
+            return Ok(true);
+        }
+
+        Ok(i32::from_file(path)? == 1)
+    }
+}
+
+/// Parses every `cpuN` line of `/proc/stat` (skipping the aggregate `cpu`
+/// line) into a per-core snapshot, keyed by core number. Cores that are
+/// offline, or otherwise absent from `/proc/stat`, simply have no entry.
+pub fn read_proc_stat_per_core() -> Result<HashMap<i8, ProcStat>, Error> {
+    let contents = fs::read_to_string("/proc/stat")?;
+    Ok(parse_proc_stat_per_core(&contents))
+}
+
+/// Pulled out of `read_proc_stat_per_core` so the parsing logic can be
+/// exercised with fixture strings instead of real `/proc/stat` contents.
+fn parse_proc_stat_per_core(contents: &str) -> HashMap<i8, ProcStat> {
+    let mut stats = HashMap::new();
+
+    for line in contents.lines() {
+        if !line.starts_with("cpu") || line.starts_with("cpu ") {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let core: i8 = match fields.next().and_then(|label| label[3..].parse().ok()) {
+            Some(core) => core,
+            None => continue,
+        };
+
+        let values: Vec<u64> = fields.filter_map(|field| field.parse().ok()).collect();
+        if values.len() < 8 {
+            continue;
+        }
+
+        stats.insert(
+            core,
+            ProcStat {
+                user: values[0],
+                nice: values[1],
+                system: values[2],
+                idle: values[3],
+                iowait: values[4],
+                irq: values[5],
+                softirq: values[6],
+                steal: values[7],
+            },
+        );
+    }
+
+    stats
+}
+
+/// Updates `cur_usage` on every CPU from the per-core `/proc/stat` deltas
+/// between two snapshots. A core missing from either snapshot (offline, or
+/// this being the first sample with nothing yet to diff against) is left
+/// at 0.0 rather than erroring.
+pub fn update_usages(
+    cpus: &mut [CPU],
+    last: &HashMap<i8, ProcStat>,
+    current: &HashMap<i8, ProcStat>,
+) -> Result<(), Error> {
+    for cpu in cpus.iter_mut() {
+        match (last.get(&cpu.number), current.get(&cpu.number)) {
+            (Some(last_stat), Some(current_stat)) => cpu.update_usage(last_stat, current_stat)?,
+            _ => cpu.cur_usage = 0.0,
+        }
+    }
+
+    Ok(())
+}
+
+/// Samples `/proc/stat`, diffs it against the previous sample in `last` to
+/// update every CPU's `cur_usage`, prints the resulting per-core usage line,
+/// then stores the fresh sample back into `last` for the next call. Meant
+/// to be called once per `config.poll_interval_ms` tick so per-core busy/idle
+/// output stays live.
+pub fn sample_cpu_usages(
+    cpus: &mut [CPU],
+    last: &mut HashMap<i8, ProcStat>,
+    config: &Config,
+) -> Result<(), Error> {
+    let current = read_proc_stat_per_core()?;
+    update_usages(cpus, last, &current)?;
+    print_cpu_usages(cpus, config);
+    *last = current;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn test_cpu(number: i8) -> CPU {
+        CPU {
+            name: format!("cpu{}", number),
+            number,
+            max_freq: 0,
+            min_freq: 0,
+            cur_freq: 0,
+            cur_temp: 0,
+            crit_temp: 0,
+            max_temp: 0,
+            cur_usage: 0.0,
+            gov: String::new(),
+            temp_sensor: None,
+            temp_sensor_checked: false,
+            freq_history: History::new(),
+        }
+    }
+
     #[test]
     fn render_unit_test() {
         let mut mock = MockSpeed::new();
-        mock.expect_read_int().return_const(42);
-        mock.expect_read_str().return_const("yflat".to_string());
+        mock.expect_read_int().returning(|_| Ok(42));
+        mock.expect_read_str()
+            .returning(|_| Ok("yflat".to_string()));
 
         // This passes, as expected
-        assert_eq!(mock.read_str("zflat"), "yflat");
-        assert_eq!(mock.read_int("abc"), 42);
+        assert_eq!(mock.read_str("zflat").unwrap(), "yflat");
+        assert_eq!(mock.read_int("abc").unwrap(), 42);
+    }
+
+    #[test]
+    fn get_temp_caches_the_lookup_even_with_no_sensor_match() {
+        // On a test box with no matching hwmon driver, `init_temp_sensor`
+        // still needs to mark the lookup as done so `get_temp` stops calling
+        // it on every subsequent tick.
+        let mut cpu = test_cpu(99);
+
+        cpu.init_temp_sensor().unwrap();
+
+        assert!(cpu.temp_sensor_checked);
+        assert!(cpu.temp_sensor.is_none());
+    }
+
+    fn test_proc_stat(idle: u64, total_non_idle: u64) -> ProcStat {
+        ProcStat {
+            user: total_non_idle,
+            nice: 0,
+            system: 0,
+            idle,
+            iowait: 0,
+            irq: 0,
+            softirq: 0,
+            steal: 0,
+        }
+    }
+
+    #[test]
+    fn parses_per_core_lines_and_skips_missing_cores() {
+        let contents = "\
+cpu  100 0 0 200 0 0 0 0
+cpu0 10 0 0 20 0 0 0 0
+cpu1 30 0 0 40 0 0 0 0
+";
+        let stats = parse_proc_stat_per_core(contents);
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[&0].user, 10);
+        assert_eq!(stats[&0].idle, 20);
+        assert_eq!(stats[&1].user, 30);
+        assert_eq!(stats[&1].idle, 40);
+    }
+
+    #[test]
+    fn skips_malformed_per_core_lines() {
+        let contents = "\
+cpu  100 0 0 200 0 0 0 0
+cpuX 10 0 0 20 0 0 0 0
+cpu0 10 0 0 20 0 0
+";
+        let stats = parse_proc_stat_per_core(contents);
+
+        assert!(stats.is_empty());
+    }
+
+    #[test]
+    fn update_usages_leaves_missing_cores_at_zero() {
+        let mut cpus = vec![test_cpu(0), test_cpu(1)];
+        cpus[0].cur_usage = 0.5;
+        cpus[1].cur_usage = 0.5;
+
+        let mut last = HashMap::new();
+        last.insert(0, test_proc_stat(20, 10));
+        let mut current = HashMap::new();
+        current.insert(0, test_proc_stat(40, 20));
+
+        update_usages(&mut cpus, &last, &current).unwrap();
+
+        assert!(cpus[0].cur_usage > 0.0);
+        assert_eq!(cpus[1].cur_usage, 0.0);
+    }
+
+    #[test]
+    fn sample_cpu_usages_refreshes_last_snapshot() {
+        let mut cpus = vec![test_cpu(0)];
+        let mut last = HashMap::new();
+        last.insert(0, test_proc_stat(20, 10));
+        let config = Config::default();
+
+        sample_cpu_usages(&mut cpus, &mut last, &config).unwrap();
+
+        // A real /proc/stat read should replace the stale fixture snapshot
+        // with whatever this machine's actual cpu0 line currently holds.
+        assert_ne!(last[&0].user, 10);
     }
 }
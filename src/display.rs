@@ -1,9 +1,75 @@
+use super::config::Config;
 use super::cpu::CPU;
 use super::power::LidState;
+use std::collections::VecDeque;
 use std::fmt::Display;
 use std::thread;
 use termion::{color, style};
 
+/// Number of samples kept for the rolling sparkline window.
+const HISTORY_CAPACITY: usize = 32;
+
+/// The block glyphs a sparkline sample is quantized into, from empty to full.
+const SPARK_GLYPHS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Fixed-capacity ring buffer of recent samples (e.g. `cur_freq` or
+/// `cur_usage`) for one CPU, used to render a per-core trend sparkline.
+#[derive(Debug, Clone)]
+pub struct History {
+    samples: VecDeque<f32>,
+}
+
+impl History {
+    pub fn new() -> History {
+        History {
+            samples: VecDeque::with_capacity(HISTORY_CAPACITY),
+        }
+    }
+
+    /// Records a new sample, evicting the oldest one once the window is full.
+    pub fn push(&mut self, value: f32) {
+        if self.samples.len() == HISTORY_CAPACITY {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(value);
+    }
+}
+
+impl Default for History {
+    fn default() -> History {
+        History::new()
+    }
+}
+
+/// Renders a history window as a horizontal bar string, normalizing each
+/// sample between the window's observed min and max.
+fn render_sparkline(history: &History) -> String {
+    if history.samples.is_empty() {
+        return String::new();
+    }
+
+    let min = history.samples.iter().cloned().fold(f32::INFINITY, f32::min);
+    let max = history
+        .samples
+        .iter()
+        .cloned()
+        .fold(f32::NEG_INFINITY, f32::max);
+
+    history
+        .samples
+        .iter()
+        .map(|&value| {
+            // Flat line: min == max, so there's nothing to normalize against.
+            let idx = if (max - min).abs() < f32::EPSILON {
+                4
+            } else {
+                (((value - min) * 8.0) / (max - min)).clamp(0.0, 8.0) as usize
+            };
+            SPARK_GLYPHS[idx]
+        })
+        .collect()
+}
+
 pub fn print_freq(f: i32, raw: bool) {
     if raw {
         println!("{}", f);
@@ -72,8 +138,10 @@ pub fn print_available_governors(available_governors: Vec<String>, raw: bool) {
     print_vec(available_governors, raw);
 }
 
-pub fn print_cpus(cpus: Vec<CPU>, name: String, raw: bool) {
-    if raw {
+pub fn print_cpus(cpus: Vec<CPU>, name: String, config: &Config) {
+    if config.basic {
+        print_cpus_basic(&cpus, &name, config);
+    } else if config.raw {
         for x in cpus {
             println!("{} {}", x.name, x.cur_freq);
         }
@@ -85,12 +153,68 @@ pub fn print_cpus(cpus: Vec<CPU>, name: String, raw: bool) {
     }
 }
 
-pub fn print_cpu(cpu: &CPU) {
+/// Collapses the per-core table into a single summarized line (average
+/// freq, hottest core temp, active governor), for headless or small
+/// terminal use.
+fn print_cpus_basic(cpus: &[CPU], name: &str, config: &Config) {
+    if cpus.is_empty() {
+        println!("{}: no CPUs found", name);
+        return;
+    }
+
+    let avg_freq: i32 = cpus.iter().map(|x| x.cur_freq).sum::<i32>() / cpus.len() as i32;
+    let hottest_temp = cpus.iter().map(|x| x.cur_temp).max().unwrap_or(0);
+    let governor = &cpus[0].gov;
+
+    if config.raw {
+        println!("{} {} {}", avg_freq, hottest_temp, governor);
+    } else {
+        println!(
+            "{}: avg {} MHz, hottest core {}C, governor {}",
+            name,
+            avg_freq / 1000,
+            hottest_temp / 1000,
+            governor
+        );
+    }
+}
+
+/// Single-line readout for one core, used when `config.basic` is set so a
+/// live-monitor tick stays readable on a small or headless terminal.
+fn print_cpu_basic(cpu: &CPU) {
+    println!(
+        "{}: {} MHz, {}C, {}",
+        cpu.name,
+        cpu.cur_freq / 1000,
+        cpu.cur_temp / 1000,
+        cpu.gov
+    );
+}
+
+pub fn print_cpu(cpu: &CPU, config: &Config) {
+    if config.basic {
+        print_cpu_basic(cpu);
+        return;
+    }
+
     let mut temp_color: String = color::Fg(color::Green).to_string();
 
-    if cpu.cur_temp / 1000 > 60 {
+    // Fall back to the configured thresholds when a sensor doesn't expose
+    // crit/max (e.g. no hwmon match was found for this core).
+    let crit_temp = if cpu.crit_temp > 0 {
+        cpu.crit_temp
+    } else {
+        config.temp_crit
+    };
+    let max_temp = if cpu.max_temp > 0 {
+        cpu.max_temp
+    } else {
+        config.temp_warn
+    };
+
+    if cpu.cur_temp >= crit_temp {
         temp_color = color::Fg(color::Red).to_string();
-    } else if cpu.cur_temp / 1000 > 40 {
+    } else if cpu.cur_temp >= max_temp {
         temp_color = color::Fg(color::Yellow).to_string();
     }
 
@@ -110,6 +234,33 @@ pub fn print_cpu(cpu: &CPU) {
     );
 }
 
+/// Prints a core's numeric readout alongside a compact trend sparkline
+/// built from its recent sample history.
+pub fn print_cpu_sparkline(cpu: &CPU, history: &History) {
+    println!(
+        "{}{}:{} {}Hz\t{}",
+        style::Bold,
+        cpu.name,
+        style::Reset,
+        cpu.cur_freq / 1000,
+        render_sparkline(history)
+    );
+}
+
+/// Prints per-core utilization, so it's clear which specific cores are busy
+/// rather than just a single system-wide number.
+pub fn print_cpu_usages(cpus: &[CPU], config: &Config) {
+    if config.raw {
+        for cpu in cpus {
+            println!("{} {:.4}", cpu.name, cpu.cur_usage);
+        }
+    } else {
+        for cpu in cpus {
+            println!("{} usage: {:.1}%", cpu.name, cpu.cur_usage * 100.0);
+        }
+    }
+}
+
 pub fn print_cpu_speeds(cpu_speeds: Vec<i32>, raw: bool) {
     print_vec(cpu_speeds, raw);
 }
@@ -121,3 +272,45 @@ pub fn print_cpu_temp(cpu_temp: Vec<i32>, raw: bool) {
 pub fn print_cpu_governors(cpu_governors: Vec<String>, raw: bool) {
     print_vec(cpu_governors, raw);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history_of(values: &[f32]) -> History {
+        let mut history = History::new();
+        for &value in values {
+            history.push(value);
+        }
+        history
+    }
+
+    #[test]
+    fn sparkline_flat_line_renders_the_mid_glyph() {
+        let history = history_of(&[42.0, 42.0, 42.0]);
+        assert_eq!(render_sparkline(&history), "▄▄▄");
+    }
+
+    #[test]
+    fn sparkline_normalizes_between_observed_min_and_max() {
+        let history = history_of(&[0.0, 4.0, 8.0]);
+        assert_eq!(render_sparkline(&history), " ▄█");
+    }
+
+    #[test]
+    fn sparkline_is_empty_with_no_samples() {
+        let history = History::new();
+        assert_eq!(render_sparkline(&history), "");
+    }
+
+    #[test]
+    fn history_evicts_the_oldest_sample_once_full() {
+        let mut history = History::new();
+        for i in 0..HISTORY_CAPACITY + 1 {
+            history.push(i as f32);
+        }
+
+        assert_eq!(history.samples.len(), HISTORY_CAPACITY);
+        assert_eq!(history.samples.front().copied(), Some(1.0));
+    }
+}
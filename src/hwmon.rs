@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::Error;
+
+/// Known hwmon driver names that expose CPU die/package temperatures.
+const CPU_HWMON_NAMES: &[&str] = &["coretemp", "k10temp", "zenpower"];
+
+/// A single hwmon temperature input, resolved from `/sys/class/hwmon/hwmonN`.
+#[derive(Debug, Clone)]
+pub struct HwmonSensor {
+    pub input_path: PathBuf,
+    pub label: String,
+    pub crit_temp: Option<i32>,
+    pub max_temp: Option<i32>,
+}
+
+/// Maps physical cores to their hwmon temperature sensor, with a
+/// package-wide sensor to fall back on when a core has no individual label.
+#[derive(Debug, Clone, Default)]
+pub struct HwmonMap {
+    per_core: HashMap<i8, HwmonSensor>,
+    package: Option<HwmonSensor>,
+}
+
+impl HwmonMap {
+    /// Scans `/sys/class/hwmon/hwmon*` for a CPU temperature driver
+    /// (coretemp, k10temp, zenpower, ...) and builds a core -> sensor map
+    /// from each `tempN_input`/`tempN_label` pair it finds.
+    pub fn discover() -> Result<HwmonMap, Error> {
+        let mut map = HwmonMap::default();
+
+        let hwmon_root = Path::new("/sys/class/hwmon");
+        if !hwmon_root.exists() {
+            return Ok(map);
+        }
+
+        for entry in fs::read_dir(hwmon_root)? {
+            let hwmon_dir = entry?.path();
+
+            let name = fs::read_to_string(hwmon_dir.join("name"))
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+
+            if !CPU_HWMON_NAMES.contains(&name.as_str()) {
+                continue;
+            }
+
+            map.collect_sensors(&hwmon_dir)?;
+        }
+
+        Ok(map)
+    }
+
+    fn collect_sensors(&mut self, hwmon_dir: &Path) -> Result<(), Error> {
+        for entry in fs::read_dir(hwmon_dir)? {
+            let input_path = entry?.path();
+            let file_name = match input_path.file_name().and_then(|n| n.to_str()) {
+                Some(file_name) => file_name.to_string(),
+                None => continue,
+            };
+
+            if !file_name.starts_with("temp") || !file_name.ends_with("_input") {
+                continue;
+            }
+
+            let index = &file_name["temp".len()..file_name.len() - "_input".len()];
+            let label = fs::read_to_string(hwmon_dir.join(format!("temp{}_label", index)))
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+
+            let sensor = HwmonSensor {
+                input_path: input_path.clone(),
+                label: label.clone(),
+                crit_temp: read_milli_temp(&hwmon_dir.join(format!("temp{}_crit", index))),
+                max_temp: read_milli_temp(&hwmon_dir.join(format!("temp{}_max", index))),
+            };
+
+            if let Some(core) = core_from_label(&label) {
+                self.per_core.insert(core, sensor);
+            } else if self.package.is_none() && is_package_label(&label) {
+                self.package = Some(sensor);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the sensor to use for a given physical core, falling back to
+    /// the package-wide sensor when the core has no individually labeled one.
+    pub fn sensor_for(&self, core: i8) -> Option<&HwmonSensor> {
+        self.per_core.get(&core).or(self.package.as_ref())
+    }
+}
+
+fn core_from_label(label: &str) -> Option<i8> {
+    label.strip_prefix("Core ")?.trim().parse().ok()
+}
+
+fn is_package_label(label: &str) -> bool {
+    let label = label.to_lowercase();
+    label.starts_with("package id") || label == "tdie" || label == "tctl"
+}
+
+fn read_milli_temp(path: &Path) -> Option<i32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sensor(label: &str) -> HwmonSensor {
+        HwmonSensor {
+            input_path: PathBuf::from("/sys/class/hwmon/hwmon0/temp1_input"),
+            label: label.to_string(),
+            crit_temp: None,
+            max_temp: None,
+        }
+    }
+
+    #[test]
+    fn sensor_for_prefers_the_per_core_label() {
+        let mut map = HwmonMap {
+            package: Some(sensor("Package id 0")),
+            ..HwmonMap::default()
+        };
+        map.per_core.insert(0, sensor("Core 0"));
+
+        let resolved = map.sensor_for(0).unwrap();
+        assert_eq!(resolved.label, "Core 0");
+    }
+
+    #[test]
+    fn sensor_for_falls_back_to_the_package_sensor() {
+        let map = HwmonMap {
+            package: Some(sensor("Package id 0")),
+            ..HwmonMap::default()
+        };
+
+        let resolved = map.sensor_for(0).unwrap();
+        assert_eq!(resolved.label, "Package id 0");
+    }
+
+    #[test]
+    fn sensor_for_is_none_when_nothing_matched() {
+        let map = HwmonMap::default();
+        assert!(map.sensor_for(0).is_none());
+    }
+
+    #[test]
+    fn core_from_label_parses_core_index() {
+        assert_eq!(core_from_label("Core 3"), Some(3));
+        assert_eq!(core_from_label("Package id 0"), None);
+    }
+
+    #[test]
+    fn is_package_label_matches_known_package_names() {
+        assert!(is_package_label("Package id 0"));
+        assert!(is_package_label("Tdie"));
+        assert!(is_package_label("tctl"));
+        assert!(!is_package_label("Core 0"));
+    }
+}
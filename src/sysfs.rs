@@ -0,0 +1,60 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use super::Error;
+
+/// Parses a value out of anything readable, modeled on procfs-core's
+/// `FromRead` trait, so sysfs reads can return a `Result` instead of
+/// panicking when a core is hot-unplugged or an attribute is absent.
+pub trait FromRead: Sized {
+    fn from_read<R: Read>(reader: R) -> Result<Self, Error>;
+
+    fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_read(File::open(path)?)
+    }
+}
+
+impl FromRead for i32 {
+    fn from_read<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let mut info = String::new();
+        reader.read_to_string(&mut info)?;
+
+        info.trim()
+            .parse()
+            .map_err(|e| Error::from(io::Error::new(io::ErrorKind::InvalidData, e)))
+    }
+}
+
+impl FromRead for String {
+    fn from_read<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let mut info = String::new();
+        reader.read_to_string(&mut info)?;
+
+        Ok(info.trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn parses_a_trimmed_integer() {
+        let value = i32::from_read(Cursor::new(b"1200000\n")).unwrap();
+        assert_eq!(value, 1_200_000);
+    }
+
+    #[test]
+    fn parses_a_trimmed_string() {
+        let value = String::from_read(Cursor::new(b"powersave\n")).unwrap();
+        assert_eq!(value, "powersave");
+    }
+
+    #[test]
+    fn invalid_integer_is_an_error_not_a_panic() {
+        let result = i32::from_read(Cursor::new(b"not a number\n"));
+        assert!(result.is_err());
+    }
+}